@@ -5,31 +5,84 @@ use std::num::NonZeroU8;
 
 use rayon::prelude::*;
 
-/// A 5 letter word. Each byte is an ascii lowercase letter a-z.
+/// The longest word length this crate supports. Bounds the on-stack buffers used by [`score`].
+pub const MAX_WORD_LEN: usize = 9;
+
+/// An `L` letter word. Each byte is an ascii lowercase letter a-z.
+///
+/// `L` is a compile time constant, but isn't tied to 5: a 4 letter Bulls-and-Cows word and a 5
+/// letter Wordle word are different instantiations (`Word<4>`, `Word<5>`) of the same type.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Word(pub [u8; 5]);
+pub struct Word<const L: usize>(pub [u8; L]);
 
-/// A Word packed into a u64.
+/// A Word packed into a u64, least significant byte first.
 struct Word64(u64);
 
-impl Display for Word {
+impl<const L: usize> Display for Word<L> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         std::str::from_utf8(&self.0).unwrap().fmt(f)
     }
 }
 
-impl Word {
+impl<const L: usize> Word<L> {
+    /// Packs the first `min(L, 8)` letters into a u64. Only meaningful for the 5 letter SIMD
+    /// fast path in [`Game::is_possible`]; other lengths never read the result.
     fn to_u64(self) -> Word64 {
-        let u = self.0[0] as u64
-            + ((self.0[1] as u64) << 8)
-            + ((self.0[2] as u64) << 16)
-            + ((self.0[3] as u64) << 24)
-            + ((self.0[4] as u64) << 32);
+        let mut u: u64 = 0;
+        for i in 0..L.min(8) {
+            u |= (self.0[i] as u64) << (i * 8);
+        }
         Word64(u)
     }
 }
 
-pub fn read_wordlist(path: &str) -> Vec<Word> {
+/// Computes the Wordle feedback tile pattern for guessing `guess` when the answer is `answer`.
+///
+/// The pattern is encoded in base 3, one trit per position: 2 for green (right letter, right
+/// spot), 1 for yellow (right letter, wrong spot), 0 for gray (letter not present, or already
+/// accounted for by other copies). The trits are combined as `sum(tile[i] * 3^i)`, giving a
+/// value in `0..3^L`.
+///
+/// Repeated letters are handled with the standard two-pass algorithm: greens are resolved first
+/// and removed from consideration, then each remaining guessed letter is matched against the
+/// remaining answer letters at most once, so a second copy of a letter the answer only has once
+/// comes back gray instead of yellow.
+pub fn score<const L: usize>(guess: Word<L>, answer: Word<L>) -> u16 {
+    debug_assert!(L <= MAX_WORD_LEN);
+    let mut pattern: u16 = 0;
+    let mut unpaired_guess = [0usize; MAX_WORD_LEN];
+    let mut unpaired_guess_len = 0;
+    let mut unpaired_answer = [0u8; MAX_WORD_LEN];
+    let mut unpaired_answer_len = 0;
+
+    for i in 0..L {
+        if guess.0[i] == answer.0[i] {
+            pattern += 2 * 3u16.pow(i as u32);
+        } else {
+            unpaired_guess[unpaired_guess_len] = i;
+            unpaired_guess_len += 1;
+            unpaired_answer[unpaired_answer_len] = answer.0[i];
+            unpaired_answer_len += 1;
+        }
+    }
+
+    for &i in &unpaired_guess[..unpaired_guess_len] {
+        let c = guess.0[i];
+        if let Some(pos) = unpaired_answer[..unpaired_answer_len]
+            .iter()
+            .position(|&a| a == c)
+        {
+            pattern += 3u16.pow(i as u32);
+            unpaired_answer_len -= 1;
+            unpaired_answer[pos] = unpaired_answer[unpaired_answer_len];
+        }
+    }
+
+    pattern
+}
+
+/// Reads a word list, keeping only lowercase ascii lines of exactly `L` letters.
+pub fn read_wordlist<const L: usize>(path: &str) -> Vec<Word<L>> {
     let f = std::fs::File::open(path).unwrap();
     let mut words = Vec::new();
     'line: for line in BufReader::new(f).lines() {
@@ -37,7 +90,7 @@ pub fn read_wordlist(path: &str) -> Vec<Word> {
         if line.is_empty() {
             continue;
         }
-        if line.len() != 5 {
+        if line.len() != L {
             continue;
         }
         for c in line.chars() {
@@ -52,36 +105,36 @@ pub fn read_wordlist(path: &str) -> Vec<Word> {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
-struct BitField(u8);
+struct BitField(u16);
 
 impl BitField {
     fn get(self, idx: usize) -> bool {
-        debug_assert!(idx < 8);
-        let idx = idx as u8;
+        debug_assert!(idx < 16);
+        let idx = idx as u16;
         self.0 & (1 << idx) != 0
     }
 
     fn set(&mut self, idx: usize) {
-        debug_assert!(idx < 8);
-        let idx = idx as u8;
+        debug_assert!(idx < 16);
+        let idx = idx as u16;
         self.0 |= 1 << idx;
     }
 
     #[allow(unused)]
     fn unset(&mut self, idx: usize) {
-        debug_assert!(idx < 8);
-        let idx = idx as u8;
-        self.0 &= (1 << idx) ^ 0xff;
+        debug_assert!(idx < 16);
+        let idx = idx as u16;
+        self.0 &= (1 << idx) ^ 0xffff;
     }
 }
 
 #[derive(Clone)]
 struct CharInfo {
-    // Bit 0-4: What fields do NOT have the char.
-    // Bit 7: Is the char NOT in the word (i.e. out).
+    // Bit 0-(MAX_WORD_LEN-1): What fields do NOT have the char.
+    // Bit 15: Is the char NOT in the word (i.e. out).
     neg: BitField,
-    // Bit 0-4: What fields DO have the char.
-    // Bit 7: Is the char IN the word.
+    // Bit 0-(MAX_WORD_LEN-1): What fields DO have the char.
+    // Bit 15: Is the char IN the word.
     pos: BitField,
 }
 
@@ -91,18 +144,18 @@ impl CharInfo {
         pos: BitField(0),
     };
 
-    fn deduce(&mut self) {
+    fn deduce(&mut self, len: usize) {
         if self.is_in() {
-            // If the char is in the word, and it's not in 4 positions, it must be in the 5th
-            // position.
+            // If the char is in the word, and it's not in all but one position, it must be in
+            // that remaining position.
             let mut negs = 0;
-            for i in 0..5 {
+            for i in 0..len {
                 if self.neg.get(i) {
                     negs += 1;
                 }
             }
-            if negs == 4 {
-                for i in 0..5 {
+            if negs == len - 1 {
+                for i in 0..len {
                     if !self.neg.get(i) {
                         self.pos.set(i);
                     }
@@ -112,89 +165,121 @@ impl CharInfo {
     }
 
     fn is_in(&self) -> bool {
-        self.pos.get(7)
+        self.pos.get(15)
     }
 
     fn is_out(&self) -> bool {
-        self.neg.get(7)
+        self.neg.get(15)
     }
 
-    fn set_in(&mut self) {
-        debug_assert!(!self.is_out());
-        self.pos.set(7);
+    /// Marks the char as in the word. Returns `false` without changing anything if it was
+    /// already marked out, i.e. this contradicts earlier info instead of refining it.
+    fn set_in(&mut self) -> bool {
+        if self.is_out() {
+            return false;
+        }
+        self.pos.set(15);
+        true
     }
 
-    fn set_out(&mut self) {
-        debug_assert!(!self.is_in());
-        self.neg.set(7)
+    /// Marks the char as out of the word. Returns `false` without changing anything if it was
+    /// already marked in, i.e. this contradicts earlier info instead of refining it.
+    fn set_out(&mut self) -> bool {
+        if self.is_in() {
+            return false;
+        }
+        self.neg.set(15);
+        true
     }
 }
 
 #[derive(Clone)]
-pub struct Game {
-    positions: [Option<NonZeroU8>; 5],
-    // Bits set if we know the char in position_packed
+pub struct Game<const L: usize> {
+    positions: [Option<NonZeroU8>; L],
+    // Bits set if we know the char in position_packed. Only meaningful for L == 5.
     position_mask: u16,
     position_packed: u64,
     chars: [CharInfo; 26],
+    // Per letter (min, max) bounds on how many times it occurs in the answer, indexed by
+    // `c - b'a'`. Defaults to (0, L), i.e. no information.
+    counts: [(u8, u8); 26],
     pub guesses: u32,
 }
 
-impl Game {
-    pub fn new() -> Game {
+impl<const L: usize> Game<L> {
+    pub fn new() -> Game<L> {
+        debug_assert!(L > 0 && L <= MAX_WORD_LEN);
         Game {
-            positions: [None; 5],
+            positions: [None; L],
             position_mask: 0,
             position_packed: 0,
             chars: [CharInfo::UNKNOWN; 26],
+            counts: [(0, L as u8); 26],
             guesses: 0,
         }
     }
 
-    fn is_possible(&self, w: Word) -> bool {
-        // This is the hot code in the program. Parts of this have simd implementations.
+    fn is_possible(&self, w: Word<L>) -> bool {
+        // This is the hot code in the program. The 5 letter case has simd implementations; L is
+        // a compile time constant, so this branch is resolved at monomorphization time and the
+        // scalar fallback below is never even compiled in for Game<5>'s fast path.
+        if L == 5 {
+            // Must have Green characters in the right places.
+            if cfg!(debug_assertions) {
+                let mut green_good = true;
+                for (i, c) in self.positions.iter().enumerate() {
+                    if let Some(c) = c {
+                        if w.0[i] != (*c).into() {
+                            green_good = false;
+                            break;
+                        }
+                    }
+                }
+                debug_assert_eq!(green_good, self.is_possible_simd_green(w.to_u64()));
+                if !green_good {
+                    return false;
+                }
+            } else if !self.is_possible_simd_green(w.to_u64()) {
+                return false;
+            }
 
-        // Must have Green characters in the right places.
-        if cfg!(debug_assertions) {
-            let mut green_good = true;
-            for (i, c) in self.positions.iter().enumerate() {
-                if let Some(c) = c {
-                    if w.0[i] != (*c).into() {
-                        green_good = false;
+            // Can't use characters we know aren't there
+            if cfg!(debug_assertions) {
+                let mut no_bad = true;
+                for i in 0..L {
+                    let c = w.0[i];
+                    let idx = (c - b'a') as usize;
+                    let info = &self.chars[idx];
+                    if info.is_out() || info.neg.get(i) {
+                        no_bad = false;
                         break;
                     }
                 }
-            }
-            debug_assert_eq!(green_good, self.is_possible_simd_green(w.to_u64()));
-            if !green_good {
+                debug_assert_eq!(no_bad, self.is_possible_simd_no_bad(w.to_u64()));
+                if !no_bad {
+                    return false;
+                }
+            } else if !self.is_possible_simd_no_bad(w.to_u64()) {
                 return false;
             }
         } else {
-            if !self.is_possible_simd_green(w.to_u64()) {
-                return false;
+            // Must have Green characters in the right places.
+            for (i, c) in self.positions.iter().enumerate() {
+                if let Some(c) = c {
+                    if w.0[i] != (*c).into() {
+                        return false;
+                    }
+                }
             }
-        }
-
-        // Can't use characters we know aren't there
-        if cfg!(debug_assertions) {
-            let mut no_bad = true;
-            for i in 0..5 {
+            // Can't use characters we know aren't there
+            for i in 0..L {
                 let c = w.0[i];
                 let idx = (c - b'a') as usize;
                 let info = &self.chars[idx];
                 if info.is_out() || info.neg.get(i) {
-                    no_bad = false;
-                    break;
+                    return false;
                 }
             }
-            debug_assert_eq!(no_bad, self.is_possible_simd_no_bad(w.to_u64()));
-            if !no_bad {
-                return false;
-            }
-        } else {
-            if !self.is_possible_simd_no_bad(w.to_u64()) {
-                return false;
-            }
         }
         // Must have Yellow characters.
         for (idx, info) in self.chars.iter().enumerate() {
@@ -205,9 +290,21 @@ impl Game {
                 }
             }
         }
+        // Must satisfy any known per letter multiplicity bounds, e.g. "at least two E's".
+        for (idx, &(min, max)) in self.counts.iter().enumerate() {
+            if min == 0 && max as usize == L {
+                continue;
+            }
+            let c = b'a' + idx as u8;
+            let count = w.0.iter().filter(|&&wc| wc == c).count() as u8;
+            if count < min || count > max {
+                return false;
+            }
+        }
         true
     }
 
+    /// SIMD fast path for `is_possible`'s green check. Only valid when `L == 5`.
     fn is_possible_simd_green(&self, w: Word64) -> bool {
         use std::arch::asm;
         use std::arch::x86_64::*;
@@ -225,18 +322,24 @@ impl Game {
         true
     }
 
+    /// SIMD fast path for `is_possible`'s "can't use a known-absent letter" check. Only valid
+    /// when `L == 5`.
     fn is_possible_simd_no_bad(&self, w: Word64) -> bool {
         use std::arch::x86_64::*;
         let wv = unsafe { _mm_set_epi64x(0, w.0 as i64) };
         let av = unsafe { _mm_set1_epi8(b'a' as i8) };
         // expanded to i32x4 to match gather
         let idx_v = unsafe { _mm_cvtepu8_epi32(_mm_sub_epi8(wv, av)) };
-        let base = (&self.chars[0].neg.0) as *const u8;
+        let base = (&self.chars[0].neg.0) as *const u16;
         let _: &CharInfo = &self.chars[0];
         const SIZE: i32 = std::mem::size_of::<CharInfo>() as i32;
         let neg_v = unsafe { _mm_i32gather_epi32::<SIZE>(base as *const i32, idx_v) };
-        let anyout_z =
-            unsafe { _mm_testz_si128(_mm_set_epi32(1 << 7, 1 << 7, 1 << 7, 1 << 7), neg_v) } as u16;
+        let anyout_z = unsafe {
+            _mm_testz_si128(
+                _mm_set_epi32(1 << 15, 1 << 15, 1 << 15, 1 << 15),
+                neg_v,
+            )
+        } as u16;
         if anyout_z == 0 {
             return false;
         }
@@ -262,8 +365,8 @@ impl Game {
     }
 
     /// Guessing this word could give new information.
-    fn is_revealing(&self, w: Word) -> bool {
-        for i in 0..5 {
+    fn is_revealing(&self, w: Word<L>) -> bool {
+        for i in 0..L {
             let c = w.0[i];
             let idx = (c - b'a') as usize;
             let info = &self.chars[idx];
@@ -282,40 +385,186 @@ impl Game {
         false
     }
 
-    pub fn guess(&mut self, answer: Word, w: Word) {
-        self.guesses += 1;
-        for i in 0..5 {
-            let c = w.0[i];
+    pub fn guess(&mut self, answer: Word<L>, w: Word<L>) {
+        let consistent = self.apply_pattern(w, score(w, answer));
+        assert!(consistent, "score() must produce feedback consistent with itself");
+    }
+
+    /// Folds real game feedback directly into the game state, without needing to know the
+    /// answer: `pattern` is a tile pattern in the same base-3 encoding `score` produces, e.g.
+    /// typed back in from an actual Wordle round. This is what lets [`Game::guess`] work from a
+    /// known answer while an interactive player can drive the same state from typed-in feedback.
+    ///
+    /// Returns `false` without changing `self` if `pattern` contradicts feedback already folded
+    /// in for this word (e.g. a letter reported gray earlier and green or yellow now, or vice
+    /// versa) — typically a fat-fingered tile in a real game. Callers taking feedback from a
+    /// human should check this and ask them to re-enter it rather than trusting it blindly.
+    #[must_use]
+    pub fn apply_pattern(&mut self, w: Word<L>, pattern: u16) -> bool {
+        // Work on a scratch copy so a contradiction partway through doesn't leave self half
+        // updated.
+        let mut next = self.clone();
+        next.guesses += 1;
+
+        // Decode the pattern into a per-position trit: 2 green, 1 yellow, 0 gray.
+        let mut p = pattern;
+        let mut trit = [0u8; MAX_WORD_LEN];
+        for slot in &mut trit[..L] {
+            *slot = (p % 3) as u8;
+            p /= 3;
+        }
+
+        let mut consistent = true;
+        for (i, &c) in w.0.iter().enumerate().take(L) {
             let idx = (c - b'a') as usize;
-            let info = &mut self.chars[idx];
-            if w.0[i] == answer.0[i] {
-                self.positions[i] = Some(w.0[i].try_into().unwrap());
-                self.position_mask |= 1 << i;
-                self.position_packed |= (w.0[i] as u64) << (i * 8);
-                info.set_in();
-                info.pos.set(i);
-            } else {
-                info.neg.set(i);
+            let info = &mut next.chars[idx];
+            match trit[i] {
+                2 => {
+                    if let Some(existing) = next.positions[i] {
+                        let existing: u8 = existing.into();
+                        consistent &= existing == c;
+                    }
+                    next.positions[i] = Some(c.try_into().unwrap());
+                    if L == 5 {
+                        next.position_mask |= 1 << i;
+                        next.position_packed |= (c as u64) << (i * 8);
+                    }
+                    consistent &= info.set_in();
+                    info.pos.set(i);
+                }
+                1 => {
+                    if let Some(existing) = next.positions[i] {
+                        let existing: u8 = existing.into();
+                        consistent &= existing != c;
+                    }
+                    info.neg.set(i);
+                    consistent &= info.set_in();
+                }
+                _ => {
+                    if let Some(existing) = next.positions[i] {
+                        let existing: u8 = existing.into();
+                        consistent &= existing != c;
+                    }
+                    info.neg.set(i);
+                }
+            }
+        }
+
+        // A gray tile means "no further unmatched copies of this letter", not necessarily that
+        // the letter is entirely absent. Tighten the count bounds for every letter this guess
+        // touched, and only mark a letter fully out once every copy of it came back gray.
+        for c in b'a'..=b'z' {
+            let guessed = w.0.iter().filter(|&&wc| wc == c).count() as u8;
+            if guessed == 0 {
+                continue;
             }
-            if answer.0.contains(&c) {
-                info.set_in();
-            } else {
-                info.set_out();
+            let hits = (0..L).filter(|&i| w.0[i] == c && trit[i] != 0).count() as u8;
+            let idx = (c - b'a') as usize;
+            let (min, max) = &mut next.counts[idx];
+            *min = (*min).max(hits);
+            if guessed > hits {
+                *max = (*max).min(hits);
+                if hits == 0 {
+                    consistent &= next.chars[idx].set_out();
+                }
             }
+            consistent &= *min <= *max;
         }
 
         for c in w.0 {
             let idx = (c - b'a') as usize;
-            let info = &mut self.chars[idx];
-            info.deduce();
+            let info = &mut next.chars[idx];
+            info.deduce(L);
+        }
+
+        if consistent {
+            *self = next;
+        }
+        consistent
+    }
+}
+
+/// A way of scoring candidate guesses against the feedback-pattern buckets in [`best_guess`].
+///
+/// Lower case names are deliberately absent: these correspond to distinct, well known objective
+/// functions rather than flags, so they're named for what they compute.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Strategy {
+    /// Minimize the largest bucket, i.e. the worst case number of answers left standing.
+    Minimax,
+    /// Minimize the expected number of answers left standing, assuming a uniform answer.
+    ExpectedRemaining,
+    /// Maximize the expected information gained, in bits.
+    Entropy,
+}
+
+impl Strategy {
+    /// The cost of guessing `guess` against `possible_words`, lower is better. `n` is
+    /// `possible_words.len()` as a float, passed in so callers computing this in a loop don't
+    /// redo the cast every time.
+    fn cost<const L: usize>(self, guess: Word<L>, possible_words: &[Word<L>], n: f64) -> f64 {
+        // This runs once per candidate guess in the hot `best_guess` loop, across every thread,
+        // so the bucket buffer needs to avoid a heap allocation per call. `L` is 5 in practice,
+        // so special case it with a fixed on-stack array (mirroring the `L == 5` fast path in
+        // `is_possible`); other lengths fall back to a `Vec` sized to `L`, not `MAX_WORD_LEN`,
+        // since a `[0u32; 3usize.pow(MAX_WORD_LEN)]` buffer would be ~80x bigger than needed.
+        if L == 5 {
+            let mut counts = [0u32; 243];
+            for &a in possible_words {
+                counts[score(guess, a) as usize] += 1;
+            }
+            self.cost_from_counts(&counts, n)
+        } else {
+            let num_patterns = 3usize.pow(L as u32);
+            let mut counts = vec![0u32; num_patterns];
+            for &a in possible_words {
+                counts[score(guess, a) as usize] += 1;
+            }
+            self.cost_from_counts(&counts, n)
+        }
+    }
+
+    /// Shared objective-function math once `counts` (the per-pattern bucket sizes for this
+    /// guess) is built, regardless of whether it came from the stack or the heap.
+    fn cost_from_counts(self, counts: &[u32], n: f64) -> f64 {
+        match self {
+            Strategy::Minimax => counts.iter().copied().max().unwrap() as f64,
+            Strategy::ExpectedRemaining => {
+                if n == 0.0 {
+                    // No known answer matches the feedback so far (the interactive player's real
+                    // answer isn't in our word list). Every bucket is empty, so there's nothing to
+                    // prefer between candidates; 0.0/0.0 would otherwise be NaN and make the
+                    // `partial_cmp` in `best_guess` panic.
+                    0.0
+                } else {
+                    counts.iter().map(|&c| (c as f64) * (c as f64)).sum::<f64>() / n
+                }
+            }
+            Strategy::Entropy => {
+                // Negated so that, like the other strategies, lower is better.
+                -counts
+                    .iter()
+                    .filter(|&&c| c > 0)
+                    .map(|&c| {
+                        let p = c as f64 / n;
+                        -p * p.log2()
+                    })
+                    .sum::<f64>()
+            }
         }
     }
 }
 
-/// Pick a guess that minimizes the maximum number of possible answers (i.e. minimax).
-pub fn best_guess(game: &Game, answer_words: &[Word], guess_words: &[Word]) -> Word {
+/// Pick a guess according to `strategy`, working from the feedback-pattern buckets each
+/// candidate guess would split the remaining possible answers into.
+pub fn best_guess<const L: usize>(
+    game: &Game<L>,
+    answer_words: &[Word<L>],
+    guess_words: &[Word<L>],
+    strategy: Strategy,
+) -> Word<L> {
     // Words won't become possible, so pre-filter the current game state.
-    let possible_words: Vec<Word> = answer_words
+    let possible_words: Vec<Word<L>> = answer_words
         .iter()
         .filter(|&&w| game.is_possible(w))
         .map(|&w| w)
@@ -325,27 +574,79 @@ pub fn best_guess(game: &Game, answer_words: &[Word], guess_words: &[Word]) -> W
         // because the only remaining word isn't able to reveal additional information.
         return possible_words[0];
     }
+    let n = possible_words.len() as f64;
+    let possible_set: std::collections::HashSet<Word<L>> =
+        possible_words.iter().copied().collect();
     // Use all words since we can still guess a word even if we know it can't be an answer.
     *guess_words
         .par_iter()
         .filter(|&&w| game.is_revealing(w))
-        .min_by_key(|&&guess| {
-            let mut max_answers = 0;
-            for &a in &possible_words {
-                let mut game = game.clone();
-                game.guess(a, guess);
-                let mut answers = 0;
-                for &na in &possible_words {
-                    if !game.is_possible(na) {
-                        continue;
-                    }
-                    answers += 1;
-                }
-                if answers > max_answers {
-                    max_answers = answers;
-                }
-            }
-            max_answers
+        .min_by(|&&a, &&b| {
+            let cost_a = strategy.cost(a, &possible_words, n);
+            let cost_b = strategy.cost(b, &possible_words, n);
+            cost_a.partial_cmp(&cost_b).unwrap().then_with(|| {
+                // On a tie, prefer a guess that could itself win outright.
+                possible_set.contains(&b).cmp(&possible_set.contains(&a))
+            })
         })
         .unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn score_grays_out_a_guessed_letter_beyond_the_answers_count() {
+        // "aab" guesses two a's, but "abc" only has one, so the extra a at index 1 comes back
+        // gray instead of yellow, while the lone b still matches at its wrong spot.
+        let pattern = score(Word(*b"aab"), Word(*b"abc"));
+        assert_eq!(pattern, 2 + 9);
+    }
+
+    #[test]
+    fn score_credits_every_copy_the_answer_actually_has() {
+        // Both words have two a's, so neither copy in the guess is gray for lack of a match.
+        let pattern = score(Word(*b"aab"), Word(*b"baa"));
+        assert_eq!(pattern, 1 + 6 + 9);
+    }
+
+    #[test]
+    fn apply_pattern_tightens_count_bounds_from_a_gray_duplicate() {
+        let mut game = Game::<3>::new();
+        let guess = Word(*b"aab");
+        let pattern = score(guess, Word(*b"abc"));
+        assert!(game.apply_pattern(guess, pattern));
+        assert_eq!(game.counts[(b'a' - b'a') as usize], (1, 1));
+        assert!(game.is_possible(Word(*b"abc")));
+        assert!(!game.is_possible(Word(*b"aaa")));
+    }
+
+    #[test]
+    fn apply_pattern_rejects_a_later_guess_that_loosens_an_exact_count() {
+        let mut game = Game::<5>::new();
+
+        // "xabcd" has a single x, at position 0, so "xxqtz"'s second x comes back gray:
+        // exactly one x, at position 0.
+        let first = Word(*b"xxqtz");
+        assert!(game.apply_pattern(first, score(first, Word(*b"xabcd"))));
+        assert_eq!(game.counts[(b'x' - b'a') as usize], (1, 1));
+
+        // "xyxab" has two x's, so this guess's third x claims a second, distinct copy that
+        // contradicts the "exactly one x" fact the first guess already pinned down.
+        let second = Word(*b"xxxyy");
+        assert!(!game.apply_pattern(second, score(second, Word(*b"xyxab"))));
+        // A rejected pattern must not clobber the fact it contradicted.
+        assert_eq!(game.counts[(b'x' - b'a') as usize], (1, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "consistent with itself")]
+    fn guess_panics_on_a_score_apply_pattern_mismatch() {
+        let mut game = Game::<5>::new();
+        let first = Word(*b"xxqtz");
+        assert!(game.apply_pattern(first, score(first, Word(*b"xabcd"))));
+        // "xyxab" genuinely has two x's, so this folds in the same contradiction as above.
+        game.guess(Word(*b"xyxab"), Word(*b"xxxyy"));
+    }
+}
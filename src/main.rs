@@ -1,24 +1,37 @@
 #![feature(stdsimd)]
 
+use std::io::BufRead;
+
 use wordle::*;
 
+/// Word length this binary plays at. The library supports any `L` up to `MAX_WORD_LEN`; this is
+/// where we pin it down to classic 5 letter Wordle.
+const WORD_LEN: usize = 5;
+
 // First guess takes a long time to compute since every word is available, but since it doesn't
 // depend on the answer, it's always aesir.
-const FIRST_GUESS: Word = Word(*b"aesir");
+const FIRST_GUESS: Word<WORD_LEN> = Word(*b"aesir");
 
-fn play(answer: Word, answer_words: &[Word], guess_words: &[Word]) -> u32 {
+fn play(
+    answer: Word<WORD_LEN>,
+    answer_words: &[Word<WORD_LEN>],
+    guess_words: &[Word<WORD_LEN>],
+    strategy: Strategy,
+) -> u32 {
     let mut game = Game::new();
-    for &w in guess_words {
-        if w == FIRST_GUESS {
-            game.guess(answer, w);
-            if w == answer {
-                return game.guesses;
+    if strategy == Strategy::Minimax {
+        for &w in guess_words {
+            if w == FIRST_GUESS {
+                game.guess(answer, w);
+                if w == answer {
+                    return game.guesses;
+                }
+                break;
             }
-            break;
         }
     }
     loop {
-        let w = best_guess(&game, answer_words, guess_words);
+        let w = best_guess(&game, answer_words, guess_words, strategy);
         game.guess(answer, w);
         if w == answer {
             return game.guesses;
@@ -26,9 +39,9 @@ fn play(answer: Word, answer_words: &[Word], guess_words: &[Word]) -> u32 {
     }
 }
 
-fn print_guess(answer: Word, guess: Word) {
+fn print_guess(answer: Word<WORD_LEN>, guess: Word<WORD_LEN>) {
     let mut out = String::new();
-    for i in 0..5 {
+    for i in 0..WORD_LEN {
         let ac = answer.0[i];
         let gc = guess.0[i];
         if ac == gc {
@@ -48,20 +61,27 @@ fn print_guess(answer: Word, guess: Word) {
     println!("{}", out);
 }
 
-fn play_verbose(answer: Word, answer_words: &[Word], guess_words: &[Word]) -> u32 {
+fn play_verbose(
+    answer: Word<WORD_LEN>,
+    answer_words: &[Word<WORD_LEN>],
+    guess_words: &[Word<WORD_LEN>],
+    strategy: Strategy,
+) -> u32 {
     let mut game = Game::new();
-    for &w in guess_words {
-        if w == FIRST_GUESS {
-            game.guess(answer, w);
-            print_guess(answer, w);
-            if w == answer {
-                return game.guesses;
+    if strategy == Strategy::Minimax {
+        for &w in guess_words {
+            if w == FIRST_GUESS {
+                game.guess(answer, w);
+                print_guess(answer, w);
+                if w == answer {
+                    return game.guesses;
+                }
+                break;
             }
-            break;
         }
     }
     loop {
-        let w = best_guess(&game, answer_words, guess_words);
+        let w = best_guess(&game, answer_words, guess_words, strategy);
         //println!("{}: guess {}", answer, w);
         game.guess(answer, w);
         print_guess(answer, w);
@@ -74,19 +94,102 @@ fn play_verbose(answer: Word, answer_words: &[Word], guess_words: &[Word]) -> u3
 /// Calculate the first guess. This takes a long time to run since the choice is unconstrained. The
 /// result is hardcoded into play.
 #[allow(unused)]
-fn find_best_first_guess(answer_words: &[Word], guess_words: &[Word]) -> Word {
+fn find_best_first_guess(
+    answer_words: &[Word<WORD_LEN>],
+    guess_words: &[Word<WORD_LEN>],
+    strategy: Strategy,
+) -> Word<WORD_LEN> {
     let game = Game::new();
-    best_guess(&game, answer_words, guess_words)
+    best_guess(&game, answer_words, guess_words, strategy)
+}
+
+/// Parse a strategy name given on the command line.
+fn parse_strategy(s: &str) -> Strategy {
+    match s {
+        "minimax" => Strategy::Minimax,
+        "expected" => Strategy::ExpectedRemaining,
+        "entropy" => Strategy::Entropy,
+        other => {
+            eprintln!("unknown strategy: {} (expected minimax, expected, or entropy)", other);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Parses a line of feedback for one guess, one char per tile: `g` green, `y` yellow, `.` gray.
+/// Returns the same base-3 pattern encoding as `score`.
+fn parse_feedback(line: &str) -> Option<u16> {
+    let line = line.trim();
+    if line.chars().count() != WORD_LEN {
+        return None;
+    }
+    let mut pattern: u16 = 0;
+    for (i, c) in line.chars().enumerate() {
+        let trit: u16 = match c {
+            'g' => 2,
+            'y' => 1,
+            '.' => 0,
+            _ => return None,
+        };
+        pattern += trit * 3u16.pow(i as u32);
+    }
+    Some(pattern)
+}
+
+/// Plays along with a real Wordle round: the solver proposes a guess, the player types back the
+/// colors they actually saw, and that feedback is folded straight into the game state without
+/// ever knowing the answer.
+fn interactive(answer_words: &[Word<WORD_LEN>], guess_words: &[Word<WORD_LEN>], strategy: Strategy) {
+    let solved_pattern = 3u16.pow(WORD_LEN as u32) - 1;
+    let mut game = Game::new();
+    let stdin = std::io::stdin();
+    while game.guesses < 6 {
+        let w = if strategy == Strategy::Minimax && game.guesses == 0 {
+            FIRST_GUESS
+        } else {
+            best_guess(&game, answer_words, guess_words, strategy)
+        };
+        println!("Guess: {}", w);
+        println!("Feedback (g=green, y=yellow, .=gray), e.g. g.y..:");
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if stdin.lock().read_line(&mut line).unwrap() == 0 {
+                return;
+            }
+            match parse_feedback(&line) {
+                Some(pattern) => {
+                    if !game.apply_pattern(w, pattern) {
+                        println!("that contradicts feedback you gave earlier for this guess: try again");
+                        continue;
+                    }
+                    if pattern == solved_pattern {
+                        println!("Solved in {} guesses!", game.guesses);
+                        return;
+                    }
+                    break;
+                }
+                None => println!("expected {} characters of g/y/.: try again", WORD_LEN),
+            }
+        }
+    }
+    println!("Out of guesses.");
 }
 
 fn main() {
     let mut args = std::env::args();
     args.next();
     let word = args.next();
+    let strategy = args.next().as_deref().map(parse_strategy).unwrap_or(Strategy::Minimax);
     // These words can be the final solution of the puzzle.
-    let answer_words = read_wordlist("answer_words.txt");
+    let answer_words = read_wordlist::<WORD_LEN>("answer_words.txt");
     // These words can be guessed.
-    let guess_words = read_wordlist("guess_words.txt");
+    let guess_words = read_wordlist::<WORD_LEN>("guess_words.txt");
+
+    if word.as_deref() == Some("interactive") {
+        interactive(&answer_words, &guess_words, strategy);
+        return;
+    }
 
     match word {
         None => {
@@ -96,7 +199,7 @@ fn main() {
             let mut sum: u64 = 0;
             let mut wins: u64 = 0;
             for &answer in &answer_words {
-                let guesses = play(answer, &answer_words, &guess_words);
+                let guesses = play(answer, &answer_words, &guess_words, strategy);
                 println!("{}: {}", answer, guesses);
                 max_guesses = max_guesses.max(guesses);
                 min_guesses = min_guesses.min(guesses);
@@ -108,7 +211,8 @@ fn main() {
             let avg = sum as f64 / answer_words.len() as f64;
             let win = wins as f64 / answer_words.len() as f64;
             println!(
-                "Words={} Max={} Min={} Avg={:.2} Win={:.2}%",
+                "Strategy={:?} Words={} Max={} Min={} Avg={:.2} Win={:.2}%",
+                strategy,
                 answer_words.len(),
                 max_guesses,
                 min_guesses,
@@ -123,15 +227,15 @@ fn main() {
                     std::process::exit(1);
                 }
             }
-            if word.len() != 5 {
-                eprintln!("word must be 5 letters: {}", word);
+            if word.len() != WORD_LEN {
+                eprintln!("word must be {} letters: {}", WORD_LEN, word);
                 std::process::exit(1);
             }
-            let word = Word(word.as_bytes().try_into().unwrap());
+            let word: Word<WORD_LEN> = Word(word.as_bytes().try_into().unwrap());
             if !answer_words.contains(&word) {
                 eprintln!("word is not a possible answer: {}", word);
             }
-            play_verbose(word, &answer_words, &guess_words);
+            play_verbose(word, &answer_words, &guess_words, strategy);
         }
     }
 }